@@ -1,8 +1,81 @@
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use serialport::SerialPort;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicU16, Ordering};
 use std::time::Duration;
 
+/// Параметры RS485: управление линией направления передачи (DE/RE) через RTS
+#[derive(Clone, Copy)]
+struct Rs485Control {
+    pre_delay_us: u64,
+    post_delay_us: u64,
+}
+
+/// Параметры кадра RTU и, опционально, управление направлением RS485
+#[derive(Clone)]
+struct SerialSettings {
+    data_bits: serialport::DataBits,
+    parity: serialport::Parity,
+    stop_bits: serialport::StopBits,
+    rs485: Option<Rs485Control>,
+}
+
+/// Переводит количество бит данных из Python (5-8) в тип `serialport`
+fn parse_data_bits(data_bits: u8) -> PyResult<serialport::DataBits> {
+    match data_bits {
+        5 => Ok(serialport::DataBits::Five),
+        6 => Ok(serialport::DataBits::Six),
+        7 => Ok(serialport::DataBits::Seven),
+        8 => Ok(serialport::DataBits::Eight),
+        other => Err(PyValueError::new_err(format!(
+            "недопустимое количество бит данных: {other} (ожидается 5-8)"
+        ))),
+    }
+}
+
+/// Переводит обозначение чётности ("N"/"E"/"O") в тип `serialport`
+fn parse_parity(parity: &str) -> PyResult<serialport::Parity> {
+    match parity.to_ascii_uppercase().as_str() {
+        "N" => Ok(serialport::Parity::None),
+        "E" => Ok(serialport::Parity::Even),
+        "O" => Ok(serialport::Parity::Odd),
+        other => Err(PyValueError::new_err(format!(
+            "недопустимая чётность: '{other}' (ожидается 'N', 'E' или 'O')"
+        ))),
+    }
+}
+
+/// Переводит количество стоп-бит (1 или 2) в тип `serialport`
+fn parse_stop_bits(stop_bits: u8) -> PyResult<serialport::StopBits> {
+    match stop_bits {
+        1 => Ok(serialport::StopBits::One),
+        2 => Ok(serialport::StopBits::Two),
+        other => Err(PyValueError::new_err(format!(
+            "недопустимое количество стоп-бит: {other} (ожидается 1 или 2)"
+        ))),
+    }
+}
+
+/// Выставляет линию RTS перед передачей и ждёт время включения приёмопередатчика
+fn rs485_assert(port: &mut Box<dyn SerialPort>, rs485: &Rs485Control) {
+    let _ = port.write_request_to_send(true);
+    if rs485.pre_delay_us > 0 {
+        std::thread::sleep(Duration::from_micros(rs485.pre_delay_us));
+    }
+}
+
+/// Снимает линию RTS после передачи, предварительно выждав `post_delay_us`
+fn rs485_deassert(port: &mut Box<dyn SerialPort>, rs485: &Rs485Control) {
+    if rs485.post_delay_us > 0 {
+        std::thread::sleep(Duration::from_micros(rs485.post_delay_us));
+    }
+    let _ = port.write_request_to_send(false);
+}
+
 /// Вычисляет CRC16 для Modbus RTU
 fn calculate_crc16(data: &[u8]) -> u16 {
     let mut crc: u16 = 0xFFFF;
@@ -35,22 +108,108 @@ fn create_command_17(device_address: u8) -> Vec<u8> {
     request
 }
 
-/// Проверяет ответ от устройства
-fn validate_response(response: &[u8], device_address: u8) -> bool {
-    if response.len() < 7 {
-        return false;
+// Иерархия типизированных исключений Modbus, видимая из Python. Базовый класс
+// ModbusError позволяет вызывающей стороне ловить `except ModbusError`, не
+// перечисляя каждый конкретный случай, а подклассы дают разобраться, что
+// именно пошло не так (нет порта, нет ответа, битый CRC, исключение устройства...).
+create_exception!(modbus_scanner_rust, ModbusError, PyException);
+create_exception!(modbus_scanner_rust, PortOpenError, ModbusError);
+create_exception!(modbus_scanner_rust, WriteError, ModbusError);
+create_exception!(modbus_scanner_rust, ReadTimeoutError, ModbusError);
+create_exception!(modbus_scanner_rust, CrcMismatchError, ModbusError);
+create_exception!(modbus_scanner_rust, ModbusExceptionError, ModbusError);
+create_exception!(modbus_scanner_rust, MalformedFrameError, ModbusError);
+
+/// Внутренняя ошибка разбора/передачи кадра Modbus
+///
+/// Отделена от `PyErr`, чтобы транспортный код (`try_device_detection`,
+/// `send_and_receive`, `send_and_receive_tcp`) мог единообразно возвращать её
+/// через `?`, а `From<FrameError> for PyErr` сам выбирал нужный класс
+/// исключения Python по месту использования.
+#[derive(Debug)]
+enum FrameError {
+    PortOpen(String),
+    Write(String),
+    ReadTimeout(String),
+    Crc,
+    Exception { function: u8, code: u8 },
+    Malformed(String),
+}
+
+impl From<FrameError> for PyErr {
+    fn from(err: FrameError) -> PyErr {
+        match err {
+            FrameError::PortOpen(msg) => PortOpenError::new_err(msg),
+            FrameError::Write(msg) => WriteError::new_err(msg),
+            FrameError::ReadTimeout(msg) => ReadTimeoutError::new_err(msg),
+            FrameError::Crc => CrcMismatchError::new_err("несовпадение CRC в ответе устройства"),
+            FrameError::Exception { function, code } => ModbusExceptionError::new_err(format!(
+                "устройство вернуло исключение Modbus для функции {:#04x}: код {}",
+                function, code
+            )),
+            FrameError::Malformed(msg) => MalformedFrameError::new_err(msg),
+        }
     }
-    
-    // Проверка адреса и функции
-    if response[0] != device_address || response[1] != 0x11 {
-        return false;
+}
+
+/// Проверяет контрольную сумму кадра RTU
+fn verify_crc(frame: &[u8]) -> Result<(), FrameError> {
+    if frame.len() < 4 {
+        return Err(FrameError::Malformed(format!(
+            "слишком короткий кадр: {} байт",
+            frame.len()
+        )));
     }
-    
-    // Проверка CRC
-    let response_crc = u16::from_le_bytes([response[response.len() - 2], response[response.len() - 1]]);
-    let calculated_crc = calculate_crc16(&response[..response.len() - 2]);
-    
-    response_crc == calculated_crc
+    let response_crc = u16::from_le_bytes([frame[frame.len() - 2], frame[frame.len() - 1]]);
+    let calculated_crc = calculate_crc16(&frame[..frame.len() - 2]);
+    if response_crc != calculated_crc {
+        return Err(FrameError::Crc);
+    }
+    Ok(())
+}
+
+/// Разобранный ответ на Report Server ID (функция 0x11): байт-каунт, флаг
+/// "запущен/остановлен" (Run Indicator Status) и данные идентификации
+/// устройства (вендор/продукт), как их вернуло само устройство
+#[derive(Clone)]
+pub struct DeviceIdentification {
+    pub byte_count: u8,
+    pub run_indicator_on: bool,
+    pub vendor_data: Vec<u8>,
+}
+
+/// Разбирает данные функции 0x11: [byte_count][vendor/product...][run indicator status]
+///
+/// `byte_count` — это поле самого ответа устройства, а не длина реально
+/// присланных данных: устройство может заявить `byte_count` больше, чем
+/// фактически прислало (для TCP это не ограничено длиной MBAP, в отличие от
+/// RTU, где длина кадра выводится из `byte_count` самим `read_rtu_frame`),
+/// так что индексация `frame[3..3 + byte_count]` не может просто доверять
+/// заявленному значению — такой ответ трактуется как битый кадр.
+fn parse_device_identification(frame: &[u8]) -> Result<DeviceIdentification, FrameError> {
+    if frame.len() < 3 {
+        return Err(FrameError::Malformed(format!(
+            "слишком короткий ответ на Report Server ID: {} байт",
+            frame.len()
+        )));
+    }
+    let byte_count = frame[2];
+    let data = frame.get(3..3 + byte_count as usize).ok_or_else(|| {
+        FrameError::Malformed(format!(
+            "устройство заявило byte_count={}, но прислало только {} байт данных",
+            byte_count,
+            frame.len() - 3
+        ))
+    })?;
+    let (vendor_data, run_indicator_on) = match data.split_last() {
+        Some((&run_indicator, vendor_data)) => (vendor_data.to_vec(), run_indicator != 0x00),
+        None => (Vec::new(), false),
+    };
+    Ok(DeviceIdentification {
+        byte_count,
+        run_indicator_on,
+        vendor_data,
+    })
 }
 
 /// Результат сканирования
@@ -59,66 +218,881 @@ pub struct ScanResult {
     pub address: u8,
     pub baudrate: u32,
     pub response: String,
+    pub identification: DeviceIdentification,
+}
+
+// Коды функций Modbus, поддерживаемые ModbusClient, плюс Report Server ID (0x11),
+// используемый сканером
+const FUNC_READ_COILS: u8 = 0x01;
+const FUNC_READ_DISCRETE_INPUTS: u8 = 0x02;
+const FUNC_READ_HOLDING_REGISTERS: u8 = 0x03;
+const FUNC_READ_INPUT_REGISTERS: u8 = 0x04;
+const FUNC_WRITE_SINGLE_COIL: u8 = 0x05;
+const FUNC_WRITE_SINGLE_REGISTER: u8 = 0x06;
+const FUNC_WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+const FUNC_REPORT_SERVER_ID: u8 = 0x11;
+
+/// Минимальный интервал тишины t3.5 (конец кадра RTU) для заданной скорости
+///
+/// 3.5 символьных времени по 11 бит/символ; выше 19200 бод символьное время
+/// становится пренебрежимо малым, поэтому стандарт Modbus фиксирует t3.5 = 1.75 мс.
+fn t35_gap(baudrate: u32) -> Duration {
+    if baudrate > 19200 {
+        Duration::from_micros(1750)
+    } else {
+        Duration::from_secs_f64(3.5 * 11.0 / baudrate as f64)
+    }
+}
+
+/// Читает один кадр RTU-ответа, зная длину кадра из его заголовка, и проверяет
+/// CRC, адрес и код функции
+///
+/// Сначала блокирующе читаются первые 3 байта (адрес, функция и байт-каунт
+/// для функций с данными — он же код исключения для ошибки). Если в ответе
+/// установлен бит исключения (`function | 0x80`), кадр фиксированной длины 5
+/// байт (адрес+функция+код+CRC). Иначе оставшаяся длина кадра вычисляется из
+/// заголовка: байт-каунт + 2 байта CRC для функций чтения/Report Server ID,
+/// либо фиксированные 8 байт кадра-подтверждения для функций записи.
+fn read_rtu_frame(
+    port: &mut Box<dyn SerialPort>,
+    expected_address: u8,
+    function: u8,
+) -> Result<Vec<u8>, FrameError> {
+    let mut header = [0u8; 3];
+    port.read_exact(&mut header)
+        .map_err(|e| FrameError::ReadTimeout(e.to_string()))?;
+    let mut frame = header.to_vec();
+
+    let mut tail = vec![0u8; rtu_frame_tail_len(&header, function)];
+    port.read_exact(&mut tail)
+        .map_err(|e| FrameError::ReadTimeout(e.to_string()))?;
+    frame.extend_from_slice(&tail);
+
+    validate_rtu_frame(&frame, expected_address, function)?;
+    Ok(frame)
+}
+
+/// Вычисляет, сколько байт ещё нужно дочитать после 3-байтового заголовка
+/// (адрес, функция, байт-каунт/код исключения), зная код функции запроса
+///
+/// Если в ответе установлен бит исключения (`function | 0x80`), кадр
+/// фиксированной длины 5 байт (адрес+функция+код+CRC), из которых 3 уже
+/// прочитаны. Иначе оставшаяся длина вычисляется из заголовка: байт-каунт + 2
+/// байта CRC для функций чтения/Report Server ID, либо фиксированные 5 байт
+/// (итого 8: адрес+функция+2+2+CRC) для кадра-подтверждения записи.
+fn rtu_frame_tail_len(header: &[u8; 3], function: u8) -> usize {
+    if header[1] == function | 0x80 {
+        return 2;
+    }
+    match function {
+        FUNC_READ_COILS
+        | FUNC_READ_DISCRETE_INPUTS
+        | FUNC_READ_HOLDING_REGISTERS
+        | FUNC_READ_INPUT_REGISTERS
+        | FUNC_REPORT_SERVER_ID => header[2] as usize + 2,
+        _ => 5,
+    }
+}
+
+/// Проверяет уже полностью прочитанный кадр RTU: CRC, адрес устройства, бит
+/// исключения и код функции
+fn validate_rtu_frame(frame: &[u8], expected_address: u8, function: u8) -> Result<(), FrameError> {
+    verify_crc(frame)?;
+
+    if frame[0] != expected_address {
+        return Err(FrameError::Malformed(format!(
+            "ответ от адреса {}, ожидался адрес {}",
+            frame[0], expected_address
+        )));
+    }
+    if frame[1] == function | 0x80 {
+        return Err(FrameError::Exception {
+            function,
+            code: frame[2],
+        });
+    }
+    if frame[1] != function {
+        return Err(FrameError::Malformed(format!(
+            "ответ содержит функцию {:#04x}, ожидалась {:#04x}",
+            frame[1], function
+        )));
+    }
+
+    Ok(())
 }
 
 /// Пытается получить ответ от устройства на указанной скорости и адресе
+///
+/// Возвращает `FrameError`, а не просто "устройство не найдено": конкретный
+/// вариант (нет порта, нет ответа, битый CRC, исключение Modbus) — это именно
+/// тот сигнал, который нужен вызывающей стороне, чтобы отличить отсутствие
+/// устройства от неверной скорости порта.
 fn try_device_detection(
     port_name: &str,
     baudrate: u32,
     device_address: u8,
     timeout_ms: u64,
-) -> Option<ScanResult> {
-    // Открываем порт с минимальными настройками
-    let mut port = match serialport::new(port_name, baudrate)
+    settings: &SerialSettings,
+) -> Result<ScanResult, FrameError> {
+    // Открываем порт с заданными параметрами кадра
+    let mut port = serialport::new(port_name, baudrate)
         .timeout(Duration::from_millis(timeout_ms))
+        .data_bits(settings.data_bits)
+        .parity(settings.parity)
+        .stop_bits(settings.stop_bits)
         .open()
-    {
-        Ok(p) => p,
-        Err(_) => return None,
-    };
-    
+        .map_err(|e| FrameError::PortOpen(format!("не удалось открыть порт {port_name}: {e}")))?;
+
     // Очищаем буферы
     let _ = port.clear(serialport::ClearBuffer::All);
-    
+
+    // Для RS485 выставляем линию направления передачи перед записью
+    if let Some(rs485) = &settings.rs485 {
+        rs485_assert(&mut port, rs485);
+    }
+
     // Создаём и отправляем запрос
     let request = create_command_17(device_address);
-    if port.write(&request).is_err() {
-        return None;
+    port.write(&request)
+        .map_err(|e| FrameError::Write(format!("ошибка записи в порт: {e}")))?;
+    let _ = port.flush();
+
+    // Возвращаем линию в режим приёма после завершения передачи
+    if let Some(rs485) = &settings.rs485 {
+        rs485_deassert(&mut port, rs485);
+    }
+
+    // Ждём межкадровый интервал t3.5 вместо фиксированной паузы в 200ms.
+    // Эта пауза не ждёт ответа устройства — `read_exact` внутри
+    // `read_rtu_frame` дальше сам блокируется на `timeout_ms`, так что на
+    // не отвечающем адресе реальные затраты на сканирование равны
+    // `t35_gap(baudrate) + timeout_ms`, а не только `timeout_ms`.
+    std::thread::sleep(t35_gap(baudrate));
+
+    // Читаем ровно столько байт, сколько требует длина кадра
+    let response = read_rtu_frame(&mut port, device_address, FUNC_REPORT_SERVER_ID)?;
+    let identification = parse_device_identification(&response)?;
+
+    Ok(ScanResult {
+        address: device_address,
+        baudrate,
+        response: response.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" "),
+        identification,
+    })
+}
+
+/// Собирает PDU для функций чтения (01/02/03/04): адрес + функция + начальный регистр + количество
+fn build_read_request(slave: u8, function: u8, start_address: u16, count: u16) -> Vec<u8> {
+    let mut request = vec![slave, function];
+    request.extend_from_slice(&start_address.to_be_bytes());
+    request.extend_from_slice(&count.to_be_bytes());
+    let crc = calculate_crc16(&request);
+    request.extend_from_slice(&crc.to_le_bytes());
+    request
+}
+
+/// Собирает PDU для Write Single Coil (0x05): значение кодируется как 0xFF00/0x0000
+fn build_write_single_coil_request(slave: u8, address: u16, value: bool) -> Vec<u8> {
+    let mut request = vec![slave, FUNC_WRITE_SINGLE_COIL];
+    request.extend_from_slice(&address.to_be_bytes());
+    request.extend_from_slice(if value { &[0xFF, 0x00] } else { &[0x00, 0x00] });
+    let crc = calculate_crc16(&request);
+    request.extend_from_slice(&crc.to_le_bytes());
+    request
+}
+
+/// Собирает PDU для Write Single Register (0x06)
+fn build_write_single_register_request(slave: u8, address: u16, value: u16) -> Vec<u8> {
+    let mut request = vec![slave, FUNC_WRITE_SINGLE_REGISTER];
+    request.extend_from_slice(&address.to_be_bytes());
+    request.extend_from_slice(&value.to_be_bytes());
+    let crc = calculate_crc16(&request);
+    request.extend_from_slice(&crc.to_le_bytes());
+    request
+}
+
+/// Наибольшее число регистров, помещающееся в PDU Write Multiple Registers:
+/// байт-каунт — один байт, значит данных не больше 255 байт, а сам Modbus
+/// дополнительно ограничивает запрос 123 регистрами (246 байт)
+const MAX_WRITE_MULTIPLE_REGISTERS: usize = 123;
+
+/// Проверяет, что количество регистров для Write Multiple Registers укладывается
+/// в байт-каунт PDU, и возвращает его как байт-каунт (`values.len() * 2`)
+///
+/// Без этой проверки `values.len() * 2` приходится приводить к `u8` напрямую,
+/// и запрос на запись больше 127 регистров молча усекается — устройство
+/// получит байт-каунт, не совпадающий с реально переданными данными.
+fn validate_write_multiple_registers_count(values_len: usize) -> PyResult<u8> {
+    if values_len == 0 || values_len > MAX_WRITE_MULTIPLE_REGISTERS {
+        return Err(PyValueError::new_err(format!(
+            "недопустимое количество регистров для записи: {values_len} (ожидается 1-{MAX_WRITE_MULTIPLE_REGISTERS})"
+        )));
+    }
+    Ok((values_len * 2) as u8)
+}
+
+/// Собирает PDU для Write Multiple Registers (0x10)
+fn build_write_multiple_registers_request(
+    slave: u8,
+    start_address: u16,
+    values: &[u16],
+) -> PyResult<Vec<u8>> {
+    let byte_count = validate_write_multiple_registers_count(values.len())?;
+    let mut request = vec![slave, FUNC_WRITE_MULTIPLE_REGISTERS];
+    request.extend_from_slice(&start_address.to_be_bytes());
+    request.extend_from_slice(&(values.len() as u16).to_be_bytes());
+    request.push(byte_count);
+    for value in values {
+        request.extend_from_slice(&value.to_be_bytes());
     }
+    let crc = calculate_crc16(&request);
+    request.extend_from_slice(&crc.to_le_bytes());
+    Ok(request)
+}
+
+/// Отправляет PDU ведомому устройству и читает кадр ответа, используя ту же
+/// основанную на длине кадра схему чтения, что и `try_device_detection`
+fn send_and_receive(
+    port_name: &str,
+    baudrate: u32,
+    timeout_ms: u64,
+    settings: &SerialSettings,
+    request: &[u8],
+) -> Result<Vec<u8>, FrameError> {
+    let mut port = serialport::new(port_name, baudrate)
+        .timeout(Duration::from_millis(timeout_ms))
+        .data_bits(settings.data_bits)
+        .parity(settings.parity)
+        .stop_bits(settings.stop_bits)
+        .open()
+        .map_err(|e| FrameError::PortOpen(format!("не удалось открыть порт {port_name}: {e}")))?;
+
+    let _ = port.clear(serialport::ClearBuffer::All);
+
+    if let Some(rs485) = &settings.rs485 {
+        rs485_assert(&mut port, rs485);
+    }
+
+    port.write(request)
+        .map_err(|e| FrameError::Write(format!("ошибка записи в порт: {e}")))?;
     let _ = port.flush();
-    
-    // Пауза для ответа устройства (200ms как в Python коде)
-    std::thread::sleep(Duration::from_millis(200));
-    
-    // Читаем ответ
-    let mut response = Vec::new();
-    let mut buf = [0u8; 256];
-    
-    // Читаем пока есть данные или не истечёт таймаут
-    loop {
-        match port.bytes_to_read() {
-            Ok(n) if n > 0 => {
-                match port.read(&mut buf[..n as usize]) {
-                    Ok(read) => {
-                        response.extend_from_slice(&buf[..read]);
-                    }
-                    Err(_) => break,
-                }
-                // Небольшая пауза между чтениями
-                std::thread::sleep(Duration::from_millis(10));
-            }
-            _ => break,
-        }
+
+    if let Some(rs485) = &settings.rs485 {
+        rs485_deassert(&mut port, rs485);
     }
-    
-    if validate_response(&response, device_address) {
-        Some(ScanResult {
-            address: device_address,
+
+    // Ждём межкадровый интервал t3.5 вместо фиксированной паузы в 200ms.
+    // Как и в `try_device_detection`, это не ожидание ответа устройства —
+    // последующее чтение кадра само блокируется на `timeout_ms`, так что
+    // общая задержка на не отвечающем устройстве равна `t35_gap(baudrate)
+    // + timeout_ms`.
+    std::thread::sleep(t35_gap(baudrate));
+
+    let slave = request[0];
+    let function = request[1];
+
+    // Читаем ровно столько байт, сколько требует длина кадра, и проверяем CRC/адрес/функцию
+    read_rtu_frame(&mut port, slave, function)
+}
+
+/// Проверяет, что в кадре действительно есть `byte_count` байт данных после
+/// заголовка [адрес][функция][байт-каунт], и возвращает срез данных
+fn take_response_data(response: &[u8]) -> Result<&[u8], FrameError> {
+    let byte_count = *response.get(2).ok_or_else(|| {
+        FrameError::Malformed(format!("слишком короткий кадр: {} байт", response.len()))
+    })? as usize;
+    response.get(3..3 + byte_count).ok_or_else(|| {
+        FrameError::Malformed(format!(
+            "байт-каунт {byte_count} не помещается в кадр из {} байт",
+            response.len()
+        ))
+    })
+}
+
+/// Разбирает ответ на функции чтения регистров (03/04) в вектор u16
+///
+/// `count` — это то, сколько регистров запросил вызывающий код, а не то,
+/// сколько устройство реально прислало: если `byte_count` в ответе меньше,
+/// чем нужно для `count` регистров, устройство ответило данными короче
+/// запроса — такой ответ трактуется как битый кадр, а не тихо возвращает
+/// меньше значений (или роняет нечётный хвостовой байт через `chunks_exact`).
+fn decode_registers_response(response: &[u8], count: u16) -> PyResult<Vec<u16>> {
+    let data = take_response_data(response)?;
+    if (count as usize) * 2 > data.len() {
+        return Err(FrameError::Malformed(format!(
+            "устройство прислало {} байт данных, недостаточно для {} запрошенных регистров",
+            data.len(),
+            count
+        ))
+        .into());
+    }
+    Ok(data
+        .chunks_exact(2)
+        .take(count as usize)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]))
+        .collect())
+}
+
+/// Разбирает ответ на функции чтения дискретных значений (01/02) в вектор bool
+///
+/// `count` — это то, сколько бит запросил вызывающий код, а не то, сколько
+/// устройство реально прислало: если `byte_count` в ответе меньше, чем нужно
+/// для `count` бит, индексация `data[i / 8]` ушла бы за пределы среза — такой
+/// ответ трактуется как битый кадр, а не как повод паниковать.
+fn decode_bits_response(response: &[u8], count: u16) -> PyResult<Vec<bool>> {
+    let data = take_response_data(response)?;
+    if (count as usize) > data.len() * 8 {
+        return Err(FrameError::Malformed(format!(
+            "устройство прислало {} байт данных, недостаточно для {} запрошенных битов",
+            data.len(),
+            count
+        ))
+        .into());
+    }
+    let mut bits = Vec::with_capacity(count as usize);
+    for i in 0..count as usize {
+        let byte = data[i / 8];
+        bits.push(byte & (1 << (i % 8)) != 0);
+    }
+    Ok(bits)
+}
+
+/// Общий клиент Modbus RTU для чтения и записи регистров/катушек известного устройства
+#[pyclass]
+pub struct ModbusClient {
+    port_name: String,
+    baudrate: u32,
+    timeout_ms: u64,
+    settings: SerialSettings,
+}
+
+#[pymethods]
+impl ModbusClient {
+    /// Создаёт клиента для работы с устройством на известном адресе и скорости
+    #[new]
+    #[pyo3(signature = (
+        port_name,
+        baudrate,
+        timeout_ms,
+        data_bits = 8,
+        parity = "N",
+        stop_bits = 1,
+        rs485_rts = false,
+        rs485_pre_delay_us = 0,
+        rs485_post_delay_us = 0,
+    ))]
+    // Плоская сигнатура нужна для именованных аргументов и значений по
+    // умолчанию на стороне Python (см. #[pyo3(signature = ...)] выше) —
+    // группировка в отдельную структуру параметров сломала бы этот вызов.
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        port_name: &str,
+        baudrate: u32,
+        timeout_ms: u64,
+        data_bits: u8,
+        parity: &str,
+        stop_bits: u8,
+        rs485_rts: bool,
+        rs485_pre_delay_us: u64,
+        rs485_post_delay_us: u64,
+    ) -> PyResult<Self> {
+        let settings = SerialSettings {
+            data_bits: parse_data_bits(data_bits)?,
+            parity: parse_parity(parity)?,
+            stop_bits: parse_stop_bits(stop_bits)?,
+            rs485: if rs485_rts {
+                Some(Rs485Control {
+                    pre_delay_us: rs485_pre_delay_us,
+                    post_delay_us: rs485_post_delay_us,
+                })
+            } else {
+                None
+            },
+        };
+        Ok(ModbusClient {
+            port_name: port_name.to_string(),
             baudrate,
-            response: response.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" "),
+            timeout_ms,
+            settings,
         })
-    } else {
-        None
+    }
+
+    /// Read Coils (0x01)
+    fn read_coils(&self, slave: u8, start_address: u16, count: u16) -> PyResult<Vec<bool>> {
+        let request = build_read_request(slave, FUNC_READ_COILS, start_address, count);
+        let response = send_and_receive(&self.port_name, self.baudrate, self.timeout_ms, &self.settings, &request)?;
+        decode_bits_response(&response, count)
+    }
+
+    /// Read Discrete Inputs (0x02)
+    fn read_discrete_inputs(&self, slave: u8, start_address: u16, count: u16) -> PyResult<Vec<bool>> {
+        let request = build_read_request(slave, FUNC_READ_DISCRETE_INPUTS, start_address, count);
+        let response = send_and_receive(&self.port_name, self.baudrate, self.timeout_ms, &self.settings, &request)?;
+        decode_bits_response(&response, count)
+    }
+
+    /// Read Holding Registers (0x03)
+    fn read_holding_registers(&self, slave: u8, start_address: u16, count: u16) -> PyResult<Vec<u16>> {
+        let request = build_read_request(slave, FUNC_READ_HOLDING_REGISTERS, start_address, count);
+        let response = send_and_receive(&self.port_name, self.baudrate, self.timeout_ms, &self.settings, &request)?;
+        decode_registers_response(&response, count)
+    }
+
+    /// Read Input Registers (0x04)
+    fn read_input_registers(&self, slave: u8, start_address: u16, count: u16) -> PyResult<Vec<u16>> {
+        let request = build_read_request(slave, FUNC_READ_INPUT_REGISTERS, start_address, count);
+        let response = send_and_receive(&self.port_name, self.baudrate, self.timeout_ms, &self.settings, &request)?;
+        decode_registers_response(&response, count)
+    }
+
+    /// Write Single Coil (0x05)
+    fn write_single_coil(&self, slave: u8, address: u16, value: bool) -> PyResult<()> {
+        let request = build_write_single_coil_request(slave, address, value);
+        send_and_receive(&self.port_name, self.baudrate, self.timeout_ms, &self.settings, &request)?;
+        Ok(())
+    }
+
+    /// Write Single Register (0x06)
+    fn write_single_register(&self, slave: u8, address: u16, value: u16) -> PyResult<()> {
+        let request = build_write_single_register_request(slave, address, value);
+        send_and_receive(&self.port_name, self.baudrate, self.timeout_ms, &self.settings, &request)?;
+        Ok(())
+    }
+
+    /// Write Multiple Registers (0x10)
+    fn write_multiple_registers(&self, slave: u8, start_address: u16, values: Vec<u16>) -> PyResult<()> {
+        let request = build_write_multiple_registers_request(slave, start_address, &values)?;
+        send_and_receive(&self.port_name, self.baudrate, self.timeout_ms, &self.settings, &request)?;
+        Ok(())
+    }
+}
+
+// --- Modbus TCP ---
+//
+// В отличие от RTU здесь нет адреса устройства и CRC в кадре: вместо этого
+// запрос оборачивается в заголовок MBAP (transaction id, protocol id, длина,
+// unit id), а PDU (код функции + данные) передаётся как есть.
+
+/// Собирает PDU для функций чтения (01/02/03/04) без адреса устройства и CRC
+fn build_tcp_read_pdu(function: u8, start_address: u16, count: u16) -> Vec<u8> {
+    let mut pdu = vec![function];
+    pdu.extend_from_slice(&start_address.to_be_bytes());
+    pdu.extend_from_slice(&count.to_be_bytes());
+    pdu
+}
+
+/// Собирает PDU для Write Single Coil (0x05)
+fn build_tcp_write_single_coil_pdu(address: u16, value: bool) -> Vec<u8> {
+    let mut pdu = vec![FUNC_WRITE_SINGLE_COIL];
+    pdu.extend_from_slice(&address.to_be_bytes());
+    pdu.extend_from_slice(if value { &[0xFF, 0x00] } else { &[0x00, 0x00] });
+    pdu
+}
+
+/// Собирает PDU для Write Single Register (0x06)
+fn build_tcp_write_single_register_pdu(address: u16, value: u16) -> Vec<u8> {
+    let mut pdu = vec![FUNC_WRITE_SINGLE_REGISTER];
+    pdu.extend_from_slice(&address.to_be_bytes());
+    pdu.extend_from_slice(&value.to_be_bytes());
+    pdu
+}
+
+/// Собирает PDU для Write Multiple Registers (0x10)
+fn build_tcp_write_multiple_registers_pdu(start_address: u16, values: &[u16]) -> PyResult<Vec<u8>> {
+    let byte_count = validate_write_multiple_registers_count(values.len())?;
+    let mut pdu = vec![FUNC_WRITE_MULTIPLE_REGISTERS];
+    pdu.extend_from_slice(&start_address.to_be_bytes());
+    pdu.extend_from_slice(&(values.len() as u16).to_be_bytes());
+    pdu.push(byte_count);
+    for value in values {
+        pdu.extend_from_slice(&value.to_be_bytes());
+    }
+    Ok(pdu)
+}
+
+/// Отправляет PDU как Modbus TCP ADU (MBAP + PDU) и возвращает ответ
+///
+/// Возвращаемое значение собрано как `[unit_id, function, ...данные]`, то
+/// есть в том же виде, в каком RTU-ADU видят `decode_registers_response` и
+/// `decode_bits_response` (адрес/unit id, функция, байт-каунт, данные) — без
+/// хвостового CRC, которого у TCP попросту нет.
+fn send_and_receive_tcp(
+    host: &str,
+    port: u16,
+    timeout_ms: u64,
+    unit_id: u8,
+    transaction_id: u16,
+    pdu: &[u8],
+) -> Result<Vec<u8>, FrameError> {
+    let mut stream = TcpStream::connect((host, port))
+        .map_err(|e| FrameError::PortOpen(format!("не удалось подключиться к {host}:{port}: {e}")))?;
+    stream
+        .set_read_timeout(Some(Duration::from_millis(timeout_ms)))
+        .map_err(|e| FrameError::PortOpen(format!("не удалось выставить таймаут чтения: {e}")))?;
+    stream
+        .set_write_timeout(Some(Duration::from_millis(timeout_ms)))
+        .map_err(|e| FrameError::PortOpen(format!("не удалось выставить таймаут записи: {e}")))?;
+
+    // Заголовок MBAP: transaction id + protocol id (всегда 0) + длина (unit id + PDU) + unit id
+    let mut adu = Vec::with_capacity(7 + pdu.len());
+    adu.extend_from_slice(&transaction_id.to_be_bytes());
+    adu.extend_from_slice(&0u16.to_be_bytes());
+    adu.extend_from_slice(&((pdu.len() + 1) as u16).to_be_bytes());
+    adu.push(unit_id);
+    adu.extend_from_slice(pdu);
+
+    stream
+        .write_all(&adu)
+        .map_err(|e| FrameError::Write(format!("ошибка записи в TCP-сокет: {e}")))?;
+
+    let mut mbap = [0u8; 7];
+    stream
+        .read_exact(&mut mbap)
+        .map_err(|e| FrameError::ReadTimeout(format!("таймаут чтения MBAP: {e}")))?;
+
+    let (response_transaction_id, length, response_unit_id) = parse_mbap_header(&mbap);
+
+    if response_transaction_id != transaction_id {
+        return Err(FrameError::Malformed(
+            "несовпадение transaction id в ответе Modbus TCP".to_string(),
+        ));
+    }
+    if length == 0 {
+        return Err(FrameError::Malformed(
+            "пустое поле длины в заголовке MBAP".to_string(),
+        ));
+    }
+
+    let mut pdu_response = vec![0u8; length - 1];
+    stream
+        .read_exact(&mut pdu_response)
+        .map_err(|e| FrameError::ReadTimeout(format!("таймаут чтения PDU: {e}")))?;
+
+    if response_unit_id != unit_id {
+        return Err(FrameError::Malformed(format!(
+            "ответ от unit id {}, ожидался {}",
+            response_unit_id, unit_id
+        )));
+    }
+    check_tcp_pdu_response(&pdu_response, pdu[0])?;
+
+    let mut response = vec![response_unit_id];
+    response.extend_from_slice(&pdu_response);
+    Ok(response)
+}
+
+/// Разбирает заголовок MBAP: transaction id, длину (unit id + PDU) и unit id
+fn parse_mbap_header(mbap: &[u8; 7]) -> (u16, usize, u8) {
+    let transaction_id = u16::from_be_bytes([mbap[0], mbap[1]]);
+    let length = u16::from_be_bytes([mbap[4], mbap[5]]) as usize;
+    let unit_id = mbap[6];
+    (transaction_id, length, unit_id)
+}
+
+/// Проверяет PDU ответа Modbus TCP: длина (MBAP может заявить нулевой PDU),
+/// бит исключения и совпадение кода функции
+///
+/// MBAP сообщает длину `unit_id + PDU`, и сервер может прислать `length == 1`
+/// (только unit id, без единого байта PDU) — без этой проверки индексация
+/// `pdu_response[0]` у пустого PDU запаниковала бы вместо того, чтобы вернуть
+/// тот же `FrameError::Malformed`, что и битый кадр RTU.
+fn check_tcp_pdu_response(pdu_response: &[u8], function: u8) -> Result<(), FrameError> {
+    if pdu_response.is_empty() {
+        return Err(FrameError::Malformed(
+            "пустой PDU в ответе Modbus TCP".to_string(),
+        ));
+    }
+    if pdu_response[0] == function | 0x80 {
+        return Err(FrameError::Exception {
+            function,
+            code: pdu_response.get(1).copied().unwrap_or(0),
+        });
+    }
+    if pdu_response[0] != function {
+        return Err(FrameError::Malformed(format!(
+            "ответ содержит функцию {:#04x}, ожидалась {:#04x}",
+            pdu_response[0], function
+        )));
+    }
+    Ok(())
+}
+
+/// Клиент Modbus TCP: те же функции, что у `ModbusClient`, но поверх `TcpStream`
+#[pyclass]
+pub struct ModbusTcpClient {
+    host: String,
+    port: u16,
+    timeout_ms: u64,
+    transaction_id: AtomicU16,
+}
+
+#[pymethods]
+impl ModbusTcpClient {
+    /// Создаёт клиента для известных host:port
+    #[new]
+    fn new(host: &str, port: u16, timeout_ms: u64) -> Self {
+        ModbusTcpClient {
+            host: host.to_string(),
+            port,
+            timeout_ms,
+            transaction_id: AtomicU16::new(0),
+        }
+    }
+
+    /// Read Coils (0x01)
+    fn read_coils(&self, unit_id: u8, start_address: u16, count: u16) -> PyResult<Vec<bool>> {
+        let pdu = build_tcp_read_pdu(FUNC_READ_COILS, start_address, count);
+        let response = send_and_receive_tcp(
+            &self.host,
+            self.port,
+            self.timeout_ms,
+            unit_id,
+            self.transaction_id.fetch_add(1, Ordering::Relaxed),
+            &pdu,
+        )?;
+        decode_bits_response(&response, count)
+    }
+
+    /// Read Discrete Inputs (0x02)
+    fn read_discrete_inputs(&self, unit_id: u8, start_address: u16, count: u16) -> PyResult<Vec<bool>> {
+        let pdu = build_tcp_read_pdu(FUNC_READ_DISCRETE_INPUTS, start_address, count);
+        let response = send_and_receive_tcp(
+            &self.host,
+            self.port,
+            self.timeout_ms,
+            unit_id,
+            self.transaction_id.fetch_add(1, Ordering::Relaxed),
+            &pdu,
+        )?;
+        decode_bits_response(&response, count)
+    }
+
+    /// Read Holding Registers (0x03)
+    fn read_holding_registers(&self, unit_id: u8, start_address: u16, count: u16) -> PyResult<Vec<u16>> {
+        let pdu = build_tcp_read_pdu(FUNC_READ_HOLDING_REGISTERS, start_address, count);
+        let response = send_and_receive_tcp(
+            &self.host,
+            self.port,
+            self.timeout_ms,
+            unit_id,
+            self.transaction_id.fetch_add(1, Ordering::Relaxed),
+            &pdu,
+        )?;
+        decode_registers_response(&response, count)
+    }
+
+    /// Read Input Registers (0x04)
+    fn read_input_registers(&self, unit_id: u8, start_address: u16, count: u16) -> PyResult<Vec<u16>> {
+        let pdu = build_tcp_read_pdu(FUNC_READ_INPUT_REGISTERS, start_address, count);
+        let response = send_and_receive_tcp(
+            &self.host,
+            self.port,
+            self.timeout_ms,
+            unit_id,
+            self.transaction_id.fetch_add(1, Ordering::Relaxed),
+            &pdu,
+        )?;
+        decode_registers_response(&response, count)
+    }
+
+    /// Write Single Coil (0x05)
+    fn write_single_coil(&self, unit_id: u8, address: u16, value: bool) -> PyResult<()> {
+        let pdu = build_tcp_write_single_coil_pdu(address, value);
+        send_and_receive_tcp(
+            &self.host,
+            self.port,
+            self.timeout_ms,
+            unit_id,
+            self.transaction_id.fetch_add(1, Ordering::Relaxed),
+            &pdu,
+        )?;
+        Ok(())
+    }
+
+    /// Write Single Register (0x06)
+    fn write_single_register(&self, unit_id: u8, address: u16, value: u16) -> PyResult<()> {
+        let pdu = build_tcp_write_single_register_pdu(address, value);
+        send_and_receive_tcp(
+            &self.host,
+            self.port,
+            self.timeout_ms,
+            unit_id,
+            self.transaction_id.fetch_add(1, Ordering::Relaxed),
+            &pdu,
+        )?;
+        Ok(())
+    }
+
+    /// Write Multiple Registers (0x10)
+    fn write_multiple_registers(&self, unit_id: u8, start_address: u16, values: Vec<u16>) -> PyResult<()> {
+        let pdu = build_tcp_write_multiple_registers_pdu(start_address, &values)?;
+        send_and_receive_tcp(
+            &self.host,
+            self.port,
+            self.timeout_ms,
+            unit_id,
+            self.transaction_id.fetch_add(1, Ordering::Relaxed),
+            &pdu,
+        )?;
+        Ok(())
+    }
+}
+
+/// Результат сканирования Modbus TCP перед конвертацией в Python-тип
+#[derive(Clone)]
+struct TcpScanResult {
+    unit_id: u8,
+    response: String,
+    identification: DeviceIdentification,
+}
+
+/// Результат сканирования Modbus TCP (адресация по unit id, а не по скорости/адресу RTU)
+#[pyclass]
+#[derive(Clone)]
+pub struct TcpScanResultPy {
+    #[pyo3(get)]
+    pub unit_id: u8,
+    #[pyo3(get)]
+    pub response: String,
+    /// Длина данных идентификации устройства (поле byte count функции 0x11)
+    #[pyo3(get)]
+    pub byte_count: u8,
+    /// Run Indicator Status: устройство сообщает, что оно запущено (running)
+    #[pyo3(get)]
+    pub run_indicator_on: bool,
+    /// Данные идентификации устройства (вендор/продукт) без байт-каунта и Run Indicator Status
+    #[pyo3(get)]
+    pub vendor_data: Vec<u8>,
+}
+
+#[pymethods]
+impl TcpScanResultPy {
+    fn __repr__(&self) -> String {
+        format!(
+            "TcpScanResult(unit_id={}, response='{}', run_indicator_on={})",
+            self.unit_id, self.response, self.run_indicator_on
+        )
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        dict.set_item("unit_id", self.unit_id)?;
+        dict.set_item("response", &self.response)?;
+        dict.set_item("byte_count", self.byte_count)?;
+        dict.set_item("run_indicator_on", self.run_indicator_on)?;
+        dict.set_item("vendor_data", &self.vendor_data)?;
+        Ok(dict.into())
+    }
+}
+
+/// Преобразует внутренний `TcpScanResult` в Python-представление
+fn tcp_scan_result_to_py(result: TcpScanResult) -> TcpScanResultPy {
+    TcpScanResultPy {
+        unit_id: result.unit_id,
+        response: result.response,
+        byte_count: result.identification.byte_count,
+        run_indicator_on: result.identification.run_indicator_on,
+        vendor_data: result.identification.vendor_data,
+    }
+}
+
+/// Пытается получить ответ на Report Server ID от данного unit id
+///
+/// Возвращает `FrameError`, а не просто "устройство не найдено" — см.
+/// `try_device_detection`, который решает ту же задачу для RTU.
+fn try_unit_detection(
+    host: &str,
+    port: u16,
+    timeout_ms: u64,
+    unit_id: u8,
+    transaction_id: u16,
+) -> Result<TcpScanResult, FrameError> {
+    let pdu = vec![FUNC_REPORT_SERVER_ID];
+    let response = send_and_receive_tcp(host, port, timeout_ms, unit_id, transaction_id, &pdu)?;
+    let identification = parse_device_identification(&response)?;
+
+    Ok(TcpScanResult {
+        unit_id,
+        response: response.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" "),
+        identification,
+    })
+}
+
+/// Сканер устройств Modbus TCP: та же идея, что `ModbusScanner`, но сканирует
+/// unit id по одному host:port вместо адресов/скоростей по серийному порту
+#[pyclass]
+pub struct ModbusTcpScanner {
+    host: String,
+    port: u16,
+    timeout_ms: u64,
+    transaction_id: AtomicU16,
+}
+
+#[pymethods]
+impl ModbusTcpScanner {
+    /// Создаёт новый сканер для известных host:port
+    #[new]
+    fn new(host: &str, port: u16, timeout_ms: u64) -> Self {
+        ModbusTcpScanner {
+            host: host.to_string(),
+            port,
+            timeout_ms,
+            transaction_id: AtomicU16::new(0),
+        }
+    }
+
+    /// Сканирует один unit id, посылая Report Server ID (0x11)
+    ///
+    /// В отличие от `scan_addresses`, которая просто пропускает unit id без
+    /// ответа, здесь ошибка пробрасывается в Python как конкретное исключение
+    /// (см. `FrameError`) — как и `ModbusScanner::scan_single` для RTU.
+    fn scan_unit(&self, unit_id: u8) -> PyResult<TcpScanResultPy> {
+        let result = try_unit_detection(
+            &self.host,
+            self.port,
+            self.timeout_ms,
+            unit_id,
+            self.transaction_id.fetch_add(1, Ordering::Relaxed),
+        )?;
+        Ok(tcp_scan_result_to_py(result))
+    }
+
+    /// Сканирует диапазон unit id (обычно 1..=247) на одном host:port
+    ///
+    /// Args:
+    ///     start_unit: Начальный unit id (включительно)
+    ///     end_unit: Конечный unit id (включительно)
+    ///     status_callback: Python функция для обновления статуса (опционально)
+    ///
+    /// Returns:
+    ///     Список найденных устройств
+    fn scan_addresses(
+        &self,
+        start_unit: u8,
+        end_unit: u8,
+        status_callback: Option<PyObject>,
+    ) -> PyResult<Vec<TcpScanResultPy>> {
+        let mut results = Vec::new();
+
+        for unit_id in start_unit..=end_unit {
+            if let Some(callback) = &status_callback {
+                Python::with_gil(|py| {
+                    let _ = callback.call1(py, (format!("Проверка unit id {}...", unit_id),));
+                });
+            }
+
+            if let Ok(result) = try_unit_detection(
+                &self.host,
+                self.port,
+                self.timeout_ms,
+                unit_id,
+                self.transaction_id.fetch_add(1, Ordering::Relaxed),
+            ) {
+                results.push(tcp_scan_result_to_py(result));
+            }
+        }
+
+        Ok(results)
     }
 }
 
@@ -127,37 +1101,90 @@ fn try_device_detection(
 pub struct ModbusScanner {
     port_name: String,
     timeout_ms: u64,
+    settings: SerialSettings,
 }
 
 #[pymethods]
 impl ModbusScanner {
     /// Создаёт новый сканер
+    ///
+    /// Args:
+    ///     port_name: Имя последовательного порта
+    ///     timeout_ms: Таймаут чтения/записи в миллисекундах — это реальный
+    ///         бюджет ожидания на один адрес: для не отвечающего устройства
+    ///         к нему добавляется ещё t3.5 (межкадровый интервал), так что
+    ///         полное сканирование диапазона адресов занимает дольше, чем
+    ///         просто `timeout_ms * количество_адресов`
+    ///     data_bits: Количество бит данных (5-8, по умолчанию 8)
+    ///     parity: Чётность 'N', 'E' или 'O' (по умолчанию 'N')
+    ///     stop_bits: Количество стоп-бит (1 или 2, по умолчанию 1)
+    ///     rs485_rts: Управлять линией RTS как линией направления RS485 (DE/RE)
+    ///     rs485_pre_delay_us: Задержка после включения передатчика перед записью, мкс
+    ///     rs485_post_delay_us: Задержка после записи перед выключением передатчика, мкс
     #[new]
-    fn new(port_name: &str, timeout_ms: u64) -> Self {
-        ModbusScanner {
+    #[pyo3(signature = (
+        port_name,
+        timeout_ms,
+        data_bits = 8,
+        parity = "N",
+        stop_bits = 1,
+        rs485_rts = false,
+        rs485_pre_delay_us = 0,
+        rs485_post_delay_us = 0,
+    ))]
+    // Плоская сигнатура нужна для именованных аргументов и значений по
+    // умолчанию на стороне Python (см. #[pyo3(signature = ...)] выше) —
+    // группировка в отдельную структуру параметров сломала бы этот вызов.
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        port_name: &str,
+        timeout_ms: u64,
+        data_bits: u8,
+        parity: &str,
+        stop_bits: u8,
+        rs485_rts: bool,
+        rs485_pre_delay_us: u64,
+        rs485_post_delay_us: u64,
+    ) -> PyResult<Self> {
+        let settings = SerialSettings {
+            data_bits: parse_data_bits(data_bits)?,
+            parity: parse_parity(parity)?,
+            stop_bits: parse_stop_bits(stop_bits)?,
+            rs485: if rs485_rts {
+                Some(Rs485Control {
+                    pre_delay_us: rs485_pre_delay_us,
+                    post_delay_us: rs485_post_delay_us,
+                })
+            } else {
+                None
+            },
+        };
+        Ok(ModbusScanner {
             port_name: port_name.to_string(),
             timeout_ms,
-        }
+            settings,
+        })
     }
-    
+
     /// Сканирует один адрес на одной скорости
-    fn scan_single(&self, address: u8, baudrate: u32) -> Option<ScanResultPy> {
-        try_device_detection(&self.port_name, baudrate, address, self.timeout_ms)
-            .map(|r| ScanResultPy {
-                address: r.address,
-                baudrate: r.baudrate,
-                response: r.response,
-            })
+    ///
+    /// В отличие от `scan_addresses`/`scan_all`, которые просто пропускают
+    /// адреса без устройства, здесь ошибка пробрасывается в Python как
+    /// конкретное исключение (см. `FrameError`) — это единственная точка
+    /// входа, где важно отличить "нет ответа" от "битый CRC".
+    fn scan_single(&self, address: u8, baudrate: u32) -> PyResult<ScanResultPy> {
+        let result = try_device_detection(&self.port_name, baudrate, address, self.timeout_ms, &self.settings)?;
+        Ok(scan_result_to_py(result))
     }
-    
+
     /// Сканирует диапазон адресов на одной скорости
-    /// 
+    ///
     /// Args:
     ///     baudrate: Скорость соединения
     ///     start_address: Начальный адрес (включительно)
     ///     end_address: Конечный адрес (включительно)
     ///     status_callback: Python функция для обновления статуса (опционально)
-    /// 
+    ///
     /// Returns:
     ///     Список найденных устройств
     fn scan_addresses(
@@ -168,7 +1195,7 @@ impl ModbusScanner {
         status_callback: Option<PyObject>,
     ) -> PyResult<Vec<ScanResultPy>> {
         let mut results = Vec::new();
-        
+
         for address in start_address..=end_address {
             // Обновляем статус через callback
             if let Some(callback) = &status_callback {
@@ -176,65 +1203,92 @@ impl ModbusScanner {
                     let _ = callback.call1(py, (format!("Проверка адреса {}...", address),));
                 });
             }
-            
-            if let Some(result) = try_device_detection(&self.port_name, baudrate, address, self.timeout_ms) {
-                results.push(ScanResultPy {
-                    address: result.address,
-                    baudrate: result.baudrate,
-                    response: result.response,
-                });
+
+            if let Ok(result) = try_device_detection(&self.port_name, baudrate, address, self.timeout_ms, &self.settings) {
+                results.push(scan_result_to_py(result));
             }
         }
-        
+
         Ok(results)
     }
-    
+
     /// Сканирует все комбинации адресов и скоростей
-    /// 
+    ///
     /// Args:
     ///     baudrates: Список скоростей для проверки
     ///     start_address: Начальный адрес (включительно)
     ///     end_address: Конечный адрес (включительно)
     ///     status_callback: Python функция для обновления статуса (опционально)
-    /// 
+    ///     lock_baudrate: Как только какой-то адрес ответит валидным кадром на
+    ///         одной из скоростей, зафиксироваться на ней и пропустить
+    ///         остальные скорости для оставшихся адресов (по умолчанию выключено)
+    ///
     /// Returns:
     ///     Список найденных устройств
+    #[pyo3(signature = (baudrates, start_address, end_address, status_callback = None, lock_baudrate = false))]
     fn scan_all(
         &self,
         baudrates: Vec<u32>,
         start_address: u8,
         end_address: u8,
         status_callback: Option<PyObject>,
+        lock_baudrate: bool,
     ) -> PyResult<Vec<ScanResultPy>> {
         let mut results = Vec::new();
-        
+        let mut locked_baudrate: Option<u32> = None;
+
         for baudrate in baudrates {
+            // Все остальные скорости пропускаем, как только зафиксировались на одной
+            if let Some(locked) = locked_baudrate {
+                if locked != baudrate {
+                    continue;
+                }
+            }
+
             if let Some(callback) = &status_callback {
                 Python::with_gil(|py| {
                     let _ = callback.call1(py, (format!("Проверка скорости {}...", baudrate),));
                 });
             }
-            
+
             for address in start_address..=end_address {
                 if let Some(callback) = &status_callback {
                     Python::with_gil(|py| {
                         let _ = callback.call1(py, (format!("  Адрес {}/{}...", address, end_address),));
                     });
                 }
-                
-                if let Some(result) = try_device_detection(&self.port_name, baudrate, address, self.timeout_ms) {
-                    results.push(ScanResultPy {
-                        address: result.address,
-                        baudrate: result.baudrate,
-                        response: result.response,
-                    });
+
+                if let Ok(result) = try_device_detection(&self.port_name, baudrate, address, self.timeout_ms, &self.settings) {
+                    results.push(scan_result_to_py(result));
+                    if lock_baudrate {
+                        locked_baudrate = Some(baudrate);
+                    }
                 }
             }
         }
-        
+
         Ok(results)
     }
-    
+
+    /// Определяет рабочую скорость для уже известного адреса устройства
+    ///
+    /// Пробует каждую скорость из `candidate_baudrates` по порядку и
+    /// останавливается на первой, где `try_device_detection` вернул `Ok` или
+    /// исключение Modbus (в обоих случаях это кадр с валидным CRC от
+    /// ожидаемого адреса) — в отличие от сканирования
+    /// по всем адресам, здесь заранее известно, что устройство на этом адресе
+    /// есть, поэтому любая успешная проверка сразу даёт ответ.
+    fn detect_baudrate(&self, address: u8, candidate_baudrates: Vec<u32>) -> Option<u32> {
+        candidate_baudrates
+            .into_iter()
+            .find(|&baudrate| {
+                matches!(
+                    try_device_detection(&self.port_name, baudrate, address, self.timeout_ms, &self.settings),
+                    Ok(_) | Err(FrameError::Exception { .. })
+                )
+            })
+    }
+
     /// Сканирует одну скорость и возвращает первое найденное устройство
     fn scan_first_found(
         &self,
@@ -249,20 +1303,28 @@ impl ModbusScanner {
                     let _ = callback.call1(py, (format!("Проверка адреса {}...", address),));
                 });
             }
-            
-            if let Some(result) = try_device_detection(&self.port_name, baudrate, address, self.timeout_ms) {
-                return Ok(Some(ScanResultPy {
-                    address: result.address,
-                    baudrate: result.baudrate,
-                    response: result.response,
-                }));
+
+            if let Ok(result) = try_device_detection(&self.port_name, baudrate, address, self.timeout_ms, &self.settings) {
+                return Ok(Some(scan_result_to_py(result)));
             }
         }
-        
+
         Ok(None)
     }
 }
 
+/// Преобразует внутренний `ScanResult` в Python-представление
+fn scan_result_to_py(result: ScanResult) -> ScanResultPy {
+    ScanResultPy {
+        address: result.address,
+        baudrate: result.baudrate,
+        response: result.response,
+        byte_count: result.identification.byte_count,
+        run_indicator_on: result.identification.run_indicator_on,
+        vendor_data: result.identification.vendor_data,
+    }
+}
+
 /// Python-представление результата сканирования
 #[pyclass]
 #[derive(Clone)]
@@ -273,22 +1335,34 @@ pub struct ScanResultPy {
     pub baudrate: u32,
     #[pyo3(get)]
     pub response: String,
+    /// Длина данных идентификации устройства (поле byte count функции 0x11)
+    #[pyo3(get)]
+    pub byte_count: u8,
+    /// Run Indicator Status: устройство сообщает, что оно запущено (running)
+    #[pyo3(get)]
+    pub run_indicator_on: bool,
+    /// Данные идентификации устройства (вендор/продукт) без байт-каунта и Run Indicator Status
+    #[pyo3(get)]
+    pub vendor_data: Vec<u8>,
 }
 
 #[pymethods]
 impl ScanResultPy {
     fn __repr__(&self) -> String {
         format!(
-            "ScanResult(address={}, baudrate={}, response='{}')",
-            self.address, self.baudrate, self.response
+            "ScanResult(address={}, baudrate={}, response='{}', run_indicator_on={})",
+            self.address, self.baudrate, self.response, self.run_indicator_on
         )
     }
-    
+
     fn to_dict(&self, py: Python) -> PyResult<PyObject> {
         let dict = PyDict::new(py);
         dict.set_item("address", self.address)?;
         dict.set_item("baudrate", self.baudrate)?;
         dict.set_item("response", &self.response)?;
+        dict.set_item("byte_count", self.byte_count)?;
+        dict.set_item("run_indicator_on", self.run_indicator_on)?;
+        dict.set_item("vendor_data", &self.vendor_data)?;
         Ok(dict.into())
     }
 }
@@ -297,8 +1371,21 @@ impl ScanResultPy {
 #[pymodule]
 fn modbus_scanner_rust(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<ModbusScanner>()?;
+    m.add_class::<ModbusClient>()?;
     m.add_class::<ScanResultPy>()?;
-    
+    m.add_class::<ModbusTcpScanner>()?;
+    m.add_class::<ModbusTcpClient>()?;
+    m.add_class::<TcpScanResultPy>()?;
+
+    // Иерархия исключений Modbus
+    m.add("ModbusError", _py.get_type::<ModbusError>())?;
+    m.add("PortOpenError", _py.get_type::<PortOpenError>())?;
+    m.add("WriteError", _py.get_type::<WriteError>())?;
+    m.add("ReadTimeoutError", _py.get_type::<ReadTimeoutError>())?;
+    m.add("CrcMismatchError", _py.get_type::<CrcMismatchError>())?;
+    m.add("ModbusExceptionError", _py.get_type::<ModbusExceptionError>())?;
+    m.add("MalformedFrameError", _py.get_type::<MalformedFrameError>())?;
+
     // Функция для быстрого поиска устройства
     #[pyfn(m)]
     #[pyo3(name = "quick_scan")]
@@ -310,9 +1397,214 @@ fn modbus_scanner_rust(_py: Python, m: &PyModule) -> PyResult<()> {
         timeout_ms: u64,
         status_callback: Option<PyObject>,
     ) -> PyResult<Vec<ScanResultPy>> {
-        let scanner = ModbusScanner::new(port_name, timeout_ms);
-        scanner.scan_all(baudrates, start_address, end_address, status_callback)
+        let scanner = ModbusScanner::new(port_name, timeout_ms, 8, "N", 1, false, 0, 0)?;
+        scanner.scan_all(baudrates, start_address, end_address, status_callback, false)
     }
-    
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framed(mut pdu: Vec<u8>) -> Vec<u8> {
+        let crc = calculate_crc16(&pdu);
+        pdu.extend_from_slice(&crc.to_le_bytes());
+        pdu
+    }
+
+    #[test]
+    fn verify_crc_accepts_matching_checksum() {
+        let frame = framed(vec![0x01, 0x03, 0x02, 0x00, 0x0A]);
+        assert!(verify_crc(&frame).is_ok());
+    }
+
+    #[test]
+    fn verify_crc_rejects_corrupted_checksum() {
+        let mut frame = framed(vec![0x01, 0x03, 0x02, 0x00, 0x0A]);
+        *frame.last_mut().unwrap() ^= 0xFF;
+        assert!(matches!(verify_crc(&frame), Err(FrameError::Crc)));
+    }
+
+    #[test]
+    fn verify_crc_rejects_too_short_frame() {
+        assert!(matches!(
+            verify_crc(&[0x01, 0x02, 0x03]),
+            Err(FrameError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn parse_device_identification_rejects_byte_count_past_frame_end() {
+        // byte_count=4 заявлен, но после него в кадре только 1 байт
+        let frame = vec![0x01, 0x11, 0x04, 0x00];
+        assert!(parse_device_identification(&frame).is_err());
+    }
+
+    #[test]
+    fn parse_device_identification_rejects_frame_shorter_than_header() {
+        let frame = vec![0x01, 0x11];
+        assert!(parse_device_identification(&frame).is_err());
+    }
+
+    #[test]
+    fn parse_device_identification_reads_vendor_data_and_run_indicator() {
+        // byte_count=3: 2 байта vendor data + run indicator status (0xFF = запущено)
+        let frame = vec![0x01, 0x11, 0x03, 0xAA, 0xBB, 0xFF];
+        let identification = parse_device_identification(&frame).unwrap();
+        assert_eq!(identification.byte_count, 3);
+        assert!(identification.run_indicator_on);
+        assert_eq!(identification.vendor_data, vec![0xAA, 0xBB]);
+    }
+
+    #[test]
+    fn t35_gap_is_fixed_above_19200_baud() {
+        assert_eq!(t35_gap(115200), Duration::from_micros(1750));
+    }
+
+    #[test]
+    fn t35_gap_scales_with_baudrate_below_19200() {
+        assert_eq!(
+            t35_gap(9600),
+            Duration::from_secs_f64(3.5 * 11.0 / 9600.0)
+        );
+    }
+
+    #[test]
+    fn rtu_frame_tail_len_for_read_response_uses_byte_count() {
+        // Адрес+функция известны, байт-каунт 5 -> 5 байт данных + 2 CRC
+        let header = [0x01, FUNC_READ_HOLDING_REGISTERS, 5];
+        assert_eq!(rtu_frame_tail_len(&header, FUNC_READ_HOLDING_REGISTERS), 7);
+    }
+
+    #[test]
+    fn rtu_frame_tail_len_for_write_ack_is_fixed() {
+        let header = [0x01, FUNC_WRITE_MULTIPLE_REGISTERS, 0x00];
+        assert_eq!(rtu_frame_tail_len(&header, FUNC_WRITE_MULTIPLE_REGISTERS), 5);
+    }
+
+    #[test]
+    fn rtu_frame_tail_len_for_exception_is_fixed_regardless_of_byte_count() {
+        let header = [0x01, FUNC_READ_HOLDING_REGISTERS | 0x80, 0xFF];
+        assert_eq!(rtu_frame_tail_len(&header, FUNC_READ_HOLDING_REGISTERS), 2);
+    }
+
+    #[test]
+    fn validate_rtu_frame_accepts_well_formed_response() {
+        let frame = framed(vec![0x01, FUNC_READ_HOLDING_REGISTERS, 0x02, 0x00, 0x0A]);
+        assert!(validate_rtu_frame(&frame, 0x01, FUNC_READ_HOLDING_REGISTERS).is_ok());
+    }
+
+    #[test]
+    fn validate_rtu_frame_rejects_unexpected_address() {
+        let frame = framed(vec![0x02, FUNC_READ_HOLDING_REGISTERS, 0x02, 0x00, 0x0A]);
+        assert!(matches!(
+            validate_rtu_frame(&frame, 0x01, FUNC_READ_HOLDING_REGISTERS),
+            Err(FrameError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn validate_rtu_frame_reports_modbus_exception() {
+        let frame = framed(vec![0x01, FUNC_READ_HOLDING_REGISTERS | 0x80, 0x02]);
+        assert!(matches!(
+            validate_rtu_frame(&frame, 0x01, FUNC_READ_HOLDING_REGISTERS),
+            Err(FrameError::Exception { function, code: 2 }) if function == FUNC_READ_HOLDING_REGISTERS
+        ));
+    }
+
+    #[test]
+    fn decode_bits_response_reads_requested_bits() {
+        let response = framed(vec![0x01, FUNC_READ_COILS, 0x01, 0xFF]);
+        let bits = decode_bits_response(&response, 8).unwrap();
+        assert_eq!(bits, vec![true; 8]);
+    }
+
+    #[test]
+    fn decode_bits_response_rejects_count_beyond_byte_count() {
+        // byte_count=1 (8 бит доступно), но вызывающая сторона запросила 64 —
+        // раньше это паниковало на индексации data[i / 8]
+        let response = vec![0x01, 0x01, 0x01, 0xFF];
+        assert!(decode_bits_response(&response, 64).is_err());
+    }
+
+    #[test]
+    fn decode_registers_response_rejects_byte_count_past_frame_end() {
+        // byte_count=4 заявлен, но в кадре только 2 байта данных
+        let response = vec![0x01, FUNC_READ_HOLDING_REGISTERS, 0x04, 0x00, 0x0A];
+        assert!(decode_registers_response(&response, 1).is_err());
+    }
+
+    #[test]
+    fn decode_registers_response_rejects_count_beyond_byte_count() {
+        // byte_count=2 (1 регистр доступен), но вызывающая сторона запросила 2
+        let response = framed(vec![0x01, FUNC_READ_HOLDING_REGISTERS, 0x02, 0x00, 0x0A]);
+        assert!(decode_registers_response(&response, 2).is_err());
+    }
+
+    #[test]
+    fn decode_registers_response_reads_requested_registers() {
+        let response = framed(vec![0x01, FUNC_READ_HOLDING_REGISTERS, 0x02, 0x00, 0x0A]);
+        assert_eq!(decode_registers_response(&response, 1).unwrap(), vec![0x000A]);
+    }
+
+    #[test]
+    fn decode_registers_response_truncates_to_requested_count() {
+        // byte_count=4 (2 регистра), но запрошен только 1 — лишний регистр отбрасывается
+        let response = framed(vec![
+            0x01,
+            FUNC_READ_HOLDING_REGISTERS,
+            0x04,
+            0x00,
+            0x0A,
+            0x00,
+            0x0B,
+        ]);
+        assert_eq!(decode_registers_response(&response, 1).unwrap(), vec![0x000A]);
+    }
+
+    #[test]
+    fn build_write_multiple_registers_request_rejects_too_many_registers() {
+        let values = vec![0u16; MAX_WRITE_MULTIPLE_REGISTERS + 1];
+        assert!(build_write_multiple_registers_request(0x01, 0x0000, &values).is_err());
+    }
+
+    #[test]
+    fn build_write_multiple_registers_request_rejects_empty_values() {
+        assert!(build_write_multiple_registers_request(0x01, 0x0000, &[]).is_err());
+    }
+
+    #[test]
+    fn build_write_multiple_registers_request_accepts_max_registers() {
+        let values = vec![0u16; MAX_WRITE_MULTIPLE_REGISTERS];
+        assert!(build_write_multiple_registers_request(0x01, 0x0000, &values).is_ok());
+    }
+
+    #[test]
+    fn parse_mbap_header_reads_transaction_length_and_unit_id() {
+        let mbap = [0x00, 0x07, 0x00, 0x00, 0x00, 0x03, 0x2A];
+        assert_eq!(parse_mbap_header(&mbap), (7, 3, 0x2A));
+    }
+
+    #[test]
+    fn check_tcp_pdu_response_rejects_empty_pdu() {
+        assert!(matches!(
+            check_tcp_pdu_response(&[], FUNC_READ_HOLDING_REGISTERS),
+            Err(FrameError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn check_tcp_pdu_response_reports_modbus_exception() {
+        assert!(matches!(
+            check_tcp_pdu_response(&[FUNC_READ_HOLDING_REGISTERS | 0x80, 0x02], FUNC_READ_HOLDING_REGISTERS),
+            Err(FrameError::Exception { code: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn check_tcp_pdu_response_accepts_matching_function() {
+        assert!(check_tcp_pdu_response(&[FUNC_READ_HOLDING_REGISTERS, 0x02, 0x00, 0x0A], FUNC_READ_HOLDING_REGISTERS).is_ok());
+    }
+}